@@ -1,45 +1,78 @@
-use anyhow::Result;
-use smithay::reexports::calloop::{EventLoop, LoopSignal};
+mod output;
+mod renderer;
+mod shell;
+mod state;
+
+use anyhow::{Context, Result};
+use smithay::reexports::{
+    calloop::EventLoop,
+    wayland_server::{Display, ListeningSocket},
+};
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
-use glow::HasContext;
-use std::env;
-use std::rc::Rc;
-use std::cell::RefCell;
+
+use state::{ClientState, State};
 
 fn main() -> Result<()> {
-    // Setup logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("setting default subscriber failed");
+    let subscriber = FmtSubscriber::builder().with_max_level(Level::INFO).finish();
+    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
     info!("🚀 Gallium compositor starting...");
 
-    // Verify llvmpipe env vars
-    let gallium_driver = env::var("GALLIUM_DRIVER").unwrap_or_else(|_| "not set".into());
-    let libgl_sw = env::var("LIBGL_ALWAYS_SOFTWARE").unwrap_or_else(|_| "not set".into());
-    info!("Environment: GALLIUM_DRIVER={}, LIBGL_ALWAYS_SOFTWARE={}", gallium_driver, libgl_sw);
-
-    // Initialize event loop
-    let mut event_loop: EventLoop<()> = EventLoop::try_new().unwrap();
-    let signal: LoopSignal = event_loop.get_signal();
-    let signal_rc = Rc::new(RefCell::new(signal));
-
-    // Initialize a headless GL context (software-rendered)
-    unsafe {
-        let gl = glow::Context::from_loader_function(|s| {
-            smithay::reexports::glutin::platform::unix::HeadlessContext::new().unwrap().get_proc_address(s)
-        });
-        info!("Initialized OpenGL context: {:?}", gl);
-    }
-
-    info!("✅ Gallium compositor initialized successfully. Using llvmpipe if configured.");
+    let renderer = renderer::init().context("initializing renderer")?;
+    info!(driver = %renderer.driver, path = ?renderer.path, "renderer ready");
+
+    let mut event_loop: EventLoop<State> = EventLoop::try_new().context("creating calloop event loop")?;
+    let mut display: Display<State> = Display::new().context("creating Wayland display")?;
+    let dh = display.handle();
+
+    let mut state = State::new(&mut display, event_loop.handle(), renderer);
+
+    // Core globals: wl_compositor / wl_subcompositor / wl_shm come from
+    // CompositorState/ShmState, wl_seat from SeatState, wl_data_device_manager
+    // from DataDeviceState — all registered in `State::new`. `xdg_wm_base` is
+    // registered by `XdgShellState::new`. We deliberately don't also
+    // advertise the deprecated `zxdg_shell_v6`: we have no intention of
+    // reimplementing its surface/toplevel semantics, and a bound-but-inert
+    // global is worse than making legacy clients fall back to `xdg_wm_base`.
+    let output = state.outputs.add_default(&dh);
+    info!(output = %output.name(), "advertising default output");
+
+    let socket = ListeningSocket::bind_auto("wayland", 1..32).context("binding Wayland listening socket")?;
+    let socket_name = socket.socket_name().map(|n| n.to_string_lossy().into_owned());
+    info!(socket = ?socket_name, "listening for Wayland clients");
+
+    event_loop
+        .handle()
+        .insert_source(socket, move |client_stream, _, state: &mut State| {
+            if let Err(e) = state
+                .display_handle
+                .insert_client(client_stream, std::sync::Arc::new(ClientState::default()))
+            {
+                tracing::warn!(error = %e, "failed to accept Wayland client connection");
+            }
+        })
+        .map_err(|e| anyhow::anyhow!("registering Wayland listening socket with calloop: {e}"))?;
+
+    event_loop
+        .handle()
+        .insert_source(
+            smithay::reexports::calloop::generic::Generic::new(
+                display.backend().poll_fd().try_clone_to_owned()?,
+                smithay::reexports::calloop::Interest::READ,
+                smithay::reexports::calloop::Mode::Level,
+            ),
+            move |_, _, state: &mut State| {
+                display.dispatch_clients(state)?;
+                Ok(smithay::reexports::calloop::PostAction::Continue)
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("registering Wayland display fd with calloop: {e}"))?;
+
+    info!("✅ Gallium compositor initialized successfully.");
     info!("Press Ctrl+C to exit.");
 
-    // Run the event loop
-    event_loop.run(None, &mut (), |_| {})?;
+    event_loop.run(None, &mut state, |_state| {})?;
 
     Ok(())
 }
@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use smithay::{
+    output::{Mode, Output, PhysicalProperties, Scale, Subpixel},
+    reexports::wayland_server::DisplayHandle,
+    utils::{Size, Transform},
+};
+use tracing::info;
+
+use crate::state::State;
+
+static NEXT_OUTPUT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// One connected output: its `wl_output` global plus the metadata window
+/// placement and HiDPI scaling need that isn't already tracked by `Output`.
+pub struct OutputEntry {
+    pub id: u32,
+    pub output: Output,
+    pub name: String,
+    pub size: Size<i32, smithay::utils::Physical>,
+    pub scale: f64,
+}
+
+/// Tracks every connected output, handling hotplug add/remove and
+/// propagating scale changes so mapped surfaces can redraw crisply.
+#[derive(Default)]
+pub struct OutputLayout {
+    entries: Vec<OutputEntry>,
+}
+
+impl OutputLayout {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Register a new output, advertise its `wl_output` global, and
+    /// re-lay-out the whole output list.
+    pub fn add(&mut self, dh: &DisplayHandle, name: &str, size: (i32, i32), scale: f64) -> Output {
+        let id = NEXT_OUTPUT_ID.fetch_add(1, Ordering::Relaxed);
+        let mode = Mode {
+            size: size.into(),
+            refresh: 60_000,
+        };
+
+        let output = Output::new(
+            name.to_string(),
+            PhysicalProperties {
+                size: (0, 0).into(),
+                subpixel: Subpixel::Unknown,
+                make: "Galactica".into(),
+                model: "Gallium".into(),
+            },
+        );
+        output.change_current_state(Some(mode), Some(Transform::Normal), Some(Scale::Fractional(scale)), Some((0, 0).into()));
+        output.set_preferred(mode);
+        output.create_global::<State>(dh);
+
+        info!(output = %name, id, width = size.0, height = size.1, scale, "output connected");
+
+        self.entries.push(OutputEntry {
+            id,
+            output: output.clone(),
+            name: name.to_string(),
+            size: size.into(),
+            scale,
+        });
+        self.position_outputs();
+        output
+    }
+
+    /// Register the single default output used before a hotplug-capable
+    /// backend (DRM/udev) is wired up.
+    pub fn add_default(&mut self, dh: &DisplayHandle) -> Output {
+        self.add(dh, "headless-0", (1920, 1080), 1.0)
+    }
+
+    /// Handle a hotplug disconnect: destroy the global and re-lay-out what remains.
+    pub fn remove(&mut self, name: &str) {
+        if let Some(idx) = self.entries.iter().position(|e| e.name == name) {
+            let entry = self.entries.remove(idx);
+            entry.output.destroy_global();
+            info!(output = %name, id = entry.id, "output disconnected");
+            self.position_outputs();
+        }
+    }
+
+    /// Update an output's scale factor. `Output::change_current_state`
+    /// re-sends `wl_output.scale`/`done` to every client bound to this
+    /// output's global, which is how already-mapped HiDPI surfaces learn to
+    /// redraw at the new scale.
+    pub fn set_scale(&mut self, name: &str, scale: f64) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.name == name) {
+            entry.scale = scale;
+            let mode = Mode {
+                size: entry.size.into(),
+                refresh: 60_000,
+            };
+            entry.output.change_current_state(Some(mode), None, Some(Scale::Fractional(scale)), None);
+            info!(output = %name, scale, "output scale changed");
+        }
+    }
+
+    /// Current output geometry, for window-placement logic to query.
+    pub fn entries(&self) -> &[OutputEntry] {
+        &self.entries
+    }
+
+    /// Lay outputs out left-to-right in connection order; good enough until
+    /// there's real output-arrangement configuration.
+    fn position_outputs(&mut self) {
+        let mut x = 0;
+        for entry in &mut self.entries {
+            entry.output.change_current_state(None, None, None, Some((x, 0).into()));
+            x += entry.size.w;
+        }
+    }
+}
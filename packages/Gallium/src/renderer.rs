@@ -0,0 +1,136 @@
+use std::env;
+use std::ffi::CString;
+
+use glow::HasContext;
+use smithay::backend::egl::{ffi, EGLContext, EGLDevice, EGLDisplay};
+use thiserror::Error;
+use tracing::{info, warn};
+
+#[derive(Debug, Error)]
+pub enum RendererError {
+    #[error("no usable hardware EGL device present")]
+    NoHardwareDevice,
+    #[error("failed to create hardware GL/EGL context: {0}")]
+    Hardware(String),
+    #[error("failed to create software (llvmpipe) GL context: {0}")]
+    Software(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPath {
+    Hardware,
+    Software,
+}
+
+pub struct Renderer {
+    pub gl: glow::Context,
+    pub egl: EGLContext,
+    pub path: RenderPath,
+    pub driver: String,
+}
+
+/// Build a GL context, preferring hardware acceleration and falling back to
+/// llvmpipe only when no hardware-backed EGL device produces a usable
+/// context — returning a typed error instead of panicking if even the
+/// software path doesn't come up. `GALLIUM_DRIVER`/`LIBGL_ALWAYS_SOFTWARE`
+/// are honored as explicit overrides that force the software path.
+pub fn init() -> Result<Renderer, RendererError> {
+    if software_forced() {
+        info!("GALLIUM_DRIVER/LIBGL_ALWAYS_SOFTWARE requested the software path explicitly");
+        return init_software();
+    }
+
+    match init_hardware() {
+        Ok(renderer) => Ok(renderer),
+        Err(e) => {
+            warn!(error = %e, "no usable hardware EGL device, falling back to llvmpipe");
+            init_software()
+        }
+    }
+}
+
+fn software_forced() -> bool {
+    let libgl_sw = env::var("LIBGL_ALWAYS_SOFTWARE").map(|v| v != "0" && !v.is_empty()).unwrap_or(false);
+    let gallium_sw = env::var("GALLIUM_DRIVER")
+        .map(|v| matches!(v.as_str(), "llvmpipe" | "softpipe"))
+        .unwrap_or(false);
+    libgl_sw || gallium_sw
+}
+
+/// Enumerate the available EGL devices and try each in turn, skipping
+/// any that are themselves a software rasterizer — some drivers expose
+/// llvmpipe as its own `EGLDevice`, and binding to one of those wouldn't be
+/// "hardware" in any sense worth reporting.
+fn init_hardware() -> Result<Renderer, RendererError> {
+    let devices = EGLDevice::enumerate().map_err(|e| RendererError::Hardware(e.to_string()))?;
+
+    for device in devices {
+        let name = device.driver_name().unwrap_or_else(|_| "unknown".to_string());
+        if is_software_driver(&name) {
+            continue;
+        }
+
+        match build_context(&device) {
+            Ok((egl, gl)) => {
+                let driver = driver_string(&gl);
+                info!(driver = %driver, path = "hardware", "GL context initialized");
+                return Ok(Renderer {
+                    gl,
+                    egl,
+                    path: RenderPath::Hardware,
+                    driver,
+                });
+            }
+            Err(e) => warn!(device = %name, error = %e, "EGL device failed to produce a context, trying next"),
+        }
+    }
+
+    Err(RendererError::NoHardwareDevice)
+}
+
+fn init_software() -> Result<Renderer, RendererError> {
+    // Mesa reads these at context-creation time, so force them before retrying.
+    env::set_var("LIBGL_ALWAYS_SOFTWARE", "1");
+    env::set_var("GALLIUM_DRIVER", "llvmpipe");
+
+    let device = EGLDevice::enumerate()
+        .map_err(|e| RendererError::Software(e.to_string()))?
+        .next()
+        .ok_or_else(|| RendererError::Software("no EGL devices present for software rendering".to_string()))?;
+
+    let (egl, gl) = build_context(&device).map_err(RendererError::Software)?;
+    let driver = driver_string(&gl);
+    info!(driver = %driver, path = "software", "GL context initialized");
+    Ok(Renderer {
+        gl,
+        egl,
+        path: RenderPath::Software,
+        driver,
+    })
+}
+
+fn build_context(device: &EGLDevice) -> Result<(EGLContext, glow::Context), String> {
+    let display = EGLDisplay::new(device).map_err(|e| e.to_string())?;
+    let context = EGLContext::new(&display).map_err(|e| e.to_string())?;
+    context.make_current().map_err(|e| e.to_string())?;
+
+    let gl = unsafe {
+        glow::Context::from_loader_function(|s| {
+            CString::new(s).map(|s| ffi::egl::GetProcAddress(s.as_ptr()) as *const _).unwrap_or(std::ptr::null())
+        })
+    };
+    Ok((context, gl))
+}
+
+fn is_software_driver(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.contains("llvmpipe") || name.contains("softpipe") || name.contains("swrast")
+}
+
+fn driver_string(gl: &glow::Context) -> String {
+    unsafe {
+        let renderer = gl.get_parameter_string(glow::RENDERER);
+        let version = gl.get_parameter_string(glow::VERSION);
+        format!("{renderer} ({version})")
+    }
+}
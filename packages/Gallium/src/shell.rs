@@ -0,0 +1,68 @@
+use smithay::{
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    wayland::{
+        compositor::with_states,
+        shell::xdg::{ToplevelSurface, XdgToplevelSurfaceData},
+    },
+};
+use tracing::info;
+
+use crate::state::State;
+
+/// Called from `XdgShellHandler::new_toplevel` when a client creates an
+/// `xdg_toplevel`. The surface isn't mapped yet — that happens on its first
+/// commit with a non-null buffer, handled in [`handle_surface_commit`].
+pub fn handle_new_toplevel(state: &mut State, surface: ToplevelSurface) {
+    surface.with_pending_state(|toplevel_state| {
+        toplevel_state.states.set(smithay::wayland::shell::xdg::State::Activated);
+    });
+    surface.send_configure();
+
+    info!(surface = ?surface.wl_surface().id(), "xdg_toplevel created, awaiting initial commit");
+    let _ = state;
+}
+
+/// Handle a `wl_surface.commit`, mapping newly-committed xdg-toplevels and
+/// leaving already-mapped ones for the renderer to pick up next frame.
+pub fn handle_surface_commit(state: &mut State, surface: &WlSurface) {
+    let Some(toplevel) = find_toplevel(state, surface) else {
+        return;
+    };
+
+    if state.mapped_toplevels.iter().any(|t| t.wl_surface() == surface) {
+        return;
+    }
+
+    let has_buffer = with_states(surface, |surface_data| {
+        surface_data
+            .cached_state
+            .current::<smithay::wayland::compositor::SurfaceAttributes>()
+            .buffer
+            .is_some()
+    });
+
+    if has_buffer {
+        info!(surface = ?surface.id(), "mapping xdg_toplevel after initial buffer commit");
+        state.mapped_toplevels.push(toplevel);
+    }
+}
+
+fn find_toplevel(state: &State, surface: &WlSurface) -> Option<ToplevelSurface> {
+    state
+        .xdg_shell_state
+        .toplevel_surfaces()
+        .iter()
+        .find(|t| t.wl_surface() == surface)
+        .cloned()
+}
+
+/// Title a client has set on its toplevel (the app-id is not returned), used for logging
+/// and (eventually) window-chrome decisions.
+pub fn toplevel_title(surface: &ToplevelSurface) -> Option<String> {
+    with_states(surface.wl_surface(), |surface_data| {
+        surface_data
+            .data_map
+            .get::<XdgToplevelSurfaceData>()
+            .and_then(|data| data.lock().unwrap().title.clone())
+    })
+}
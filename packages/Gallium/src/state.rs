@@ -0,0 +1,155 @@
+use smithay::{
+    delegate_compositor, delegate_data_device, delegate_output, delegate_seat, delegate_shm, delegate_xdg_shell,
+    input::{Seat, SeatHandler, SeatState},
+    reexports::{
+        calloop::LoopHandle,
+        wayland_server::{
+            backend::{ClientData, ClientId, DisconnectReason},
+            protocol::wl_surface::WlSurface,
+            Display, DisplayHandle,
+        },
+    },
+    wayland::{
+        compositor::{CompositorClientState, CompositorHandler, CompositorState},
+        output::OutputManagerState,
+        selection::data_device::{ClientDndGrabHandler, DataDeviceHandler, DataDeviceState, ServerDndGrabHandler},
+        shell::xdg::{ToplevelSurface, XdgShellHandler, XdgShellState},
+        shm::{ShmHandler, ShmState},
+    },
+};
+
+use crate::output::OutputLayout;
+use crate::renderer::Renderer;
+
+/// Top-level compositor state, mutated by smithay's dispatch callbacks as
+/// clients talk to us over their Wayland connections.
+pub struct State {
+    pub display_handle: DisplayHandle,
+    pub loop_handle: LoopHandle<'static, State>,
+
+    pub compositor_state: CompositorState,
+    pub shm_state: ShmState,
+    pub seat_state: SeatState<State>,
+    pub output_manager_state: OutputManagerState,
+    pub data_device_state: DataDeviceState,
+    pub xdg_shell_state: XdgShellState,
+
+    pub seat: Seat<State>,
+    pub outputs: OutputLayout,
+    pub renderer: Renderer,
+
+    /// Toplevels that have completed an initial commit and are considered mapped.
+    pub mapped_toplevels: Vec<ToplevelSurface>,
+}
+
+impl State {
+    pub fn new(display: &mut Display<State>, loop_handle: LoopHandle<'static, State>, renderer: Renderer) -> Self {
+        let dh = display.handle();
+
+        let compositor_state = CompositorState::new::<State>(&dh);
+        let shm_state = ShmState::new::<State>(&dh, Vec::new());
+        let mut seat_state = SeatState::new();
+        // `wl_data_device_manager` rides along with the seat it's advertised for.
+        let output_manager_state = OutputManagerState::new_with_xdg_output::<State>(&dh);
+        let data_device_state = DataDeviceState::new::<State>(&dh);
+        let xdg_shell_state = XdgShellState::new::<State>(&dh);
+
+        let seat = seat_state.new_wl_seat(&dh, "seat0");
+
+        Self {
+            display_handle: dh,
+            loop_handle,
+            compositor_state,
+            shm_state,
+            seat_state,
+            output_manager_state,
+            data_device_state,
+            xdg_shell_state,
+            seat,
+            outputs: OutputLayout::new(),
+            renderer,
+            mapped_toplevels: Vec::new(),
+        }
+    }
+}
+
+/// Per-client bookkeeping required by `CompositorHandler`.
+#[derive(Default)]
+pub struct ClientState {
+    pub compositor_state: CompositorClientState,
+}
+
+impl ClientData for ClientState {
+    fn initialized(&self, _client_id: ClientId) {}
+    fn disconnected(&self, _client_id: ClientId, _reason: DisconnectReason) {}
+}
+
+impl CompositorHandler for State {
+    fn compositor_state(&mut self) -> &mut CompositorState {
+        &mut self.compositor_state
+    }
+
+    fn client_compositor_state<'a>(&self, client: &'a smithay::reexports::wayland_server::Client) -> &'a CompositorClientState {
+        client.get_data::<ClientState>().map(|d| &d.compositor_state).unwrap()
+    }
+
+    fn commit(&mut self, surface: &WlSurface) {
+        // Let smithay's generic buffer-attachment bookkeeping run first so
+        // `with_states` sees an up-to-date `SurfaceAttributes`.
+        smithay::backend::renderer::utils::on_commit_buffer_handler::<State>(surface);
+
+        crate::shell::handle_surface_commit(self, surface);
+    }
+}
+
+delegate_compositor!(State);
+
+impl ShmHandler for State {
+    fn shm_state(&self) -> &ShmState {
+        &self.shm_state
+    }
+}
+
+delegate_shm!(State);
+
+impl SeatHandler for State {
+    type KeyboardFocus = WlSurface;
+    type PointerFocus = WlSurface;
+    type TouchFocus = WlSurface;
+
+    fn seat_state(&mut self) -> &mut SeatState<State> {
+        &mut self.seat_state
+    }
+}
+
+delegate_seat!(State);
+
+impl DataDeviceHandler for State {
+    fn data_device_state(&self) -> &DataDeviceState {
+        &self.data_device_state
+    }
+}
+
+impl ClientDndGrabHandler for State {}
+impl ServerDndGrabHandler for State {}
+
+delegate_data_device!(State);
+delegate_output!(State);
+
+impl XdgShellHandler for State {
+    fn xdg_shell_state(&mut self) -> &mut XdgShellState {
+        &mut self.xdg_shell_state
+    }
+
+    fn new_toplevel(&mut self, surface: ToplevelSurface) {
+        crate::shell::handle_new_toplevel(self, surface);
+    }
+
+    fn new_popup(&mut self, _surface: smithay::wayland::shell::xdg::PopupSurface, _positioner: smithay::wayland::shell::xdg::PositionerState) {
+        // Popups are not tracked: no configure is sent and they are never positioned or mapped.
+    }
+
+    fn grab(&mut self, _surface: smithay::wayland::shell::xdg::PopupSurface, _seat: smithay::reexports::wayland_server::protocol::wl_seat::WlSeat, _serial: smithay::utils::Serial) {}
+}
+
+delegate_xdg_shell!(State);
@@ -1,6 +1,11 @@
 use anyhow::Result;
 use tracing::{info, Level};
 
+/// This binary is intentionally left as a placeholder: the real Wayland
+/// compositor (smithay state, core globals, output handling, rendering) was
+/// built out in the `Gallium` crate, not here. Run `Gallium` to get an
+/// actual compositor; this crate is not wired up to share that
+/// implementation and doing so is out of scope for that work.
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
@@ -9,10 +14,7 @@ async fn main() -> Result<()> {
         .init();
 
     info!("🌌 Galactica Compositor v{}", env!("CARGO_PKG_VERSION"));
-    info!("Initializing compositor...");
-
-    // TODO: Initialize Wayland compositor
-    println!("Compositor not yet implemented - this is a placeholder");
+    info!("This binary is an unimplemented placeholder; run the Gallium crate for the real compositor.");
 
     Ok(())
 }
\ No newline at end of file
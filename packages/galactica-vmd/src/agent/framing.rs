@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_vsock::VsockStream;
+
+/// Length-prefixed JSON framing for the vsock control channel: a 4-byte
+/// big-endian length followed by that many bytes of JSON body. Plain framing
+/// rather than a full tonic/gRPC stack keeps the guest-side agent small,
+/// while still leaving room to add new `Request`/`Response` variants without
+/// breaking older guests (unknown fields are just ignored by serde).
+pub async fn write_frame<T: Serialize>(stream: &mut VsockStream, value: &T) -> Result<()> {
+    let body = serde_json::to_vec(value).context("serializing vsock frame")?;
+    stream
+        .write_all(&(body.len() as u32).to_be_bytes())
+        .await
+        .context("writing vsock frame length")?;
+    stream.write_all(&body).await.context("writing vsock frame body")?;
+    Ok(())
+}
+
+pub async fn read_frame<T: for<'de> Deserialize<'de>>(stream: &mut VsockStream) -> Result<T> {
+    let mut len_buf = [0u8; 4];
+    stream
+        .read_exact(&mut len_buf)
+        .await
+        .context("reading vsock frame length")?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+    let mut body = vec![0u8; len];
+    stream
+        .read_exact(&mut body)
+        .await
+        .context("reading vsock frame body")?;
+    serde_json::from_slice(&body).context("decoding vsock frame body")
+}
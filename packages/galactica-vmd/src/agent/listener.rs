@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio_vsock::{VsockAddr, VsockListener};
+use tracing::info;
+
+use crate::config::VmConfig;
+
+use super::framing::read_frame;
+use super::ports;
+
+/// The "I'm up" message the guest agent sends once it has booted.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GuestReady {
+    pub hostname: String,
+}
+
+/// A container status report the guest agent sends after provisioning completes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContainerStatusReport {
+    pub running: bool,
+    pub detail: String,
+}
+
+/// Open the startup-port listener, accepting from any guest CID. Must be called before
+/// `vm.boot` so the guest's connection isn't missed.
+pub fn bind_startup_listener() -> Result<VsockListener> {
+    VsockListener::bind(VsockAddr::new(libc::VMADDR_CID_ANY, ports::STARTUP))
+        .context("binding guest startup vsock listener")
+}
+
+/// Accept the guest's readiness connection and decode its hello frame.
+pub async fn accept_ready(listener: VsockListener, config: &VmConfig, timeout: Duration) -> Result<GuestReady> {
+    let (mut stream, addr) = tokio::time::timeout(timeout, listener.accept())
+        .await
+        .with_context(|| format!("timed out waiting for {} to report ready", config.name))??;
+
+    let ready: GuestReady = read_frame(&mut stream).await?;
+    info!(vm = %config.name, guest_cid = addr.cid(), hostname = %ready.hostname, "guest reported ready");
+    Ok(ready)
+}
+
+/// Open the container-status listener, accepting from any guest CID. Must be called before
+/// issuing `LaunchContainer` so the guest's report isn't missed.
+pub fn bind_container_status_listener() -> Result<VsockListener> {
+    VsockListener::bind(VsockAddr::new(libc::VMADDR_CID_ANY, ports::CONTAINER_STATUS))
+        .context("binding container status vsock listener")
+}
+
+/// Accept one container status report from the guest.
+pub async fn accept_container_status(
+    listener: VsockListener,
+    config: &VmConfig,
+    timeout: Duration,
+) -> Result<ContainerStatusReport> {
+    let (mut stream, _addr) = tokio::time::timeout(timeout, listener.accept())
+        .await
+        .with_context(|| format!("timed out waiting for {} to report container status", config.name))??;
+
+    let report: ContainerStatusReport = read_frame(&mut stream).await?;
+    info!(vm = %config.name, running = report.running, detail = %report.detail, "guest reported container status");
+    Ok(report)
+}
+
+/// Best-effort readiness probe for `List`: true if the guest agent currently
+/// accepts connections on its command port.
+pub async fn probe_ready(config: &VmConfig) -> bool {
+    tokio::time::timeout(
+        Duration::from_millis(200),
+        tokio_vsock::VsockStream::connect(config.cid, ports::COMMAND),
+    )
+    .await
+    .map(|r| r.is_ok())
+    .unwrap_or(false)
+}
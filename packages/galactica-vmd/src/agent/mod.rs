@@ -0,0 +1,21 @@
+//! Host<->guest control channel over virtio-vsock.
+//!
+//! Modeled on the maitred/garcon/tremplin split used by Crostini-style
+//! container VMs: the guest dials the host back on a well-known startup
+//! port once it's booted, the host issues RPCs (launch a container, mount a
+//! shared dir, run a command) on a command port, and the guest reports
+//! container status asynchronously on its own port. Messages are
+//! length-prefixed JSON so the protocol can grow new fields/variants without
+//! breaking older guests.
+
+mod framing;
+mod listener;
+mod rpc;
+
+pub mod ports;
+
+pub use listener::{
+    accept_container_status, accept_ready, bind_container_status_listener, bind_startup_listener, probe_ready,
+    ContainerStatusReport, GuestReady,
+};
+pub use rpc::{launch_container, mount_shared_dir, run_command, Request, Response};
@@ -0,0 +1,14 @@
+//! Well-known vsock ports for the host<->guest control channel, mirroring the
+//! maitred/garcon/tremplin port scheme used by Crostini-style container VMs:
+//! one port the guest dials home on at boot, one the host issues RPCs on,
+//! and one the guest uses to report container status asynchronously.
+
+/// The guest connects back to the host on this port once its init/agent is up.
+pub const STARTUP: u32 = 7035;
+
+/// The host issues RPCs (`LaunchContainer`, `MountSharedDir`, `RunCommand`) to
+/// the guest agent on this port.
+pub const COMMAND: u32 = 7036;
+
+/// The guest reports container state transitions to the host on this port.
+pub const CONTAINER_STATUS: u32 = 7037;
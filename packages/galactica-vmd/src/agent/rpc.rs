@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio_vsock::VsockStream;
+
+use crate::config::VmConfig;
+
+use super::framing::{read_frame, write_frame};
+use super::ports;
+
+/// A single RPC sent from the host to the guest agent over the command port.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Request {
+    LaunchContainer {
+        alias: String,
+        server: String,
+        user: String,
+    },
+    MountSharedDir {
+        tag: String,
+        target: String,
+    },
+    RunCommand {
+        command: String,
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Response {
+    Ok { message: String },
+    Err { message: String },
+}
+
+async fn call(config: &VmConfig, request: &Request) -> Result<Response> {
+    let mut stream = VsockStream::connect(config.cid, ports::COMMAND)
+        .await
+        .with_context(|| format!("connecting to guest agent on VM {} (cid {})", config.name, config.cid))?;
+
+    write_frame(&mut stream, request).await?;
+    read_frame(&mut stream).await
+}
+
+pub async fn launch_container(config: &VmConfig, alias: &str, server: &str, user: &str) -> Result<Response> {
+    call(
+        config,
+        &Request::LaunchContainer {
+            alias: alias.to_string(),
+            server: server.to_string(),
+            user: user.to_string(),
+        },
+    )
+    .await
+}
+
+pub async fn mount_shared_dir(config: &VmConfig, tag: &str, target: &str) -> Result<Response> {
+    call(
+        config,
+        &Request::MountSharedDir {
+            tag: tag.to_string(),
+            target: target.to_string(),
+        },
+    )
+    .await
+}
+
+pub async fn run_command(config: &VmConfig, command: &str, args: Vec<String>) -> Result<Response> {
+    call(
+        config,
+        &Request::RunCommand {
+            command: command.to_string(),
+            args,
+        },
+    )
+    .await
+}
@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::container::ContainerSpec;
+
+/// A single disk attachment, e.g. `--disk /var/lib/vms/root.img`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskConfig {
+    pub path: PathBuf,
+}
+
+/// A single network device attachment, e.g. `--net tap0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetConfig {
+    pub tap: String,
+}
+
+/// A host directory shared into the guest via virtiofs, e.g.
+/// `--shared-dir work=/home/user/work`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedDir {
+    /// virtiofs mount tag, also used as the guest mount point under `/mnt`.
+    pub tag: String,
+    pub host_path: PathBuf,
+}
+
+impl SharedDir {
+    /// Where the guest agent is asked to mount this tag.
+    pub fn guest_target(&self) -> String {
+        format!("/mnt/{}", self.tag)
+    }
+}
+
+/// Resolved configuration for a single VM, persisted as TOML under the state
+/// dir so it can be re-read by `Start` on a later invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmConfig {
+    pub name: String,
+    pub kernel: PathBuf,
+    pub cmdline: String,
+    pub cpus: u8,
+    pub memory_mb: u64,
+    pub disks: Vec<DiskConfig>,
+    pub nets: Vec<NetConfig>,
+    /// Guest CID for the vsock control channel. CIDs 0-2 are reserved by the
+    /// kernel, so VMs are assigned sequentially starting at 3.
+    pub cid: u32,
+    #[serde(default)]
+    pub shared_dirs: Vec<SharedDir>,
+    /// Container to provision and boot straight into, if any.
+    #[serde(default)]
+    pub default_container: Option<ContainerSpec>,
+}
+
+/// Lowest vsock CID usable by a guest; 0-2 are reserved (hypervisor, local, host).
+const FIRST_GUEST_CID: u32 = 3;
+
+impl VmConfig {
+    /// Directory holding one TOML file and one API socket per known VM.
+    pub fn state_dir() -> Result<PathBuf> {
+        let base = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .context("could not determine a state directory for this platform")?;
+        Ok(base.join("galactica-vmd"))
+    }
+
+    fn toml_path(state_dir: &Path, name: &str) -> PathBuf {
+        state_dir.join(format!("{name}.toml"))
+    }
+
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::toml_path(&Self::state_dir()?, name);
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading VM config at {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("parsing VM config at {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let dir = Self::state_dir()?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("creating state dir {}", dir.display()))?;
+        let raw = toml::to_string_pretty(self).context("serializing VM config")?;
+        let path = Self::toml_path(&dir, &self.name);
+        std::fs::write(&path, raw).with_context(|| format!("writing VM config to {}", path.display()))
+    }
+
+    /// Names of all VMs with a persisted config, sorted for stable `List` output.
+    pub fn list() -> Result<Vec<String>> {
+        let dir = Self::state_dir()?;
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(&dir).with_context(|| format!("reading {}", dir.display()))? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(stem.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Path to this VM's cloud-hypervisor API socket.
+    pub fn socket_path(&self) -> Result<PathBuf> {
+        Ok(Self::state_dir()?.join(format!("{}.sock", self.name)))
+    }
+
+    /// Path to the virtiofsd socket serving `tag` for this VM.
+    pub fn virtiofsd_socket_path(&self, tag: &str) -> Result<PathBuf> {
+        Ok(Self::state_dir()?.join(format!("{}-{tag}.virtiofs.sock", self.name)))
+    }
+
+    /// Next unused guest CID, one higher than the highest CID already
+    /// assigned to an existing VM.
+    pub fn next_cid() -> Result<u32> {
+        let mut next = FIRST_GUEST_CID;
+        for name in Self::list()? {
+            if let Ok(existing) = Self::load(&name) {
+                next = next.max(existing.cid + 1);
+            }
+        }
+        Ok(next)
+    }
+}
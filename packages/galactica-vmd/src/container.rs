@@ -0,0 +1,118 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::agent;
+use crate::config::VmConfig;
+
+/// Defaults used when `create-container` is invoked with no overrides, so a
+/// bare `galactica-vmd create-container <vm>` works out of the box.
+pub const DEFAULT_IMAGE_ALIAS: &str = "debian/stretch";
+pub const DEFAULT_IMAGE_SERVER: &str = "https://images.galactica.invalid";
+pub const DEFAULT_CONTAINER_USER: &str = "galactica";
+
+/// How long `create-container` waits for the guest to report provisioning status.
+const CONTAINER_STATUS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A container to provision inside a guest: a named image pulled from an
+/// image server, run as a given user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerSpec {
+    pub alias: String,
+    pub server: String,
+    pub user: String,
+}
+
+impl Default for ContainerSpec {
+    fn default() -> Self {
+        Self {
+            alias: DEFAULT_IMAGE_ALIAS.to_string(),
+            server: DEFAULT_IMAGE_SERVER.to_string(),
+            user: DEFAULT_CONTAINER_USER.to_string(),
+        }
+    }
+}
+
+/// Lifecycle state of a container inside a VM, tracked alongside
+/// [`crate::state::VmState`] and surfaced by `List`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContainerState {
+    #[default]
+    None,
+    Pulling,
+    Running,
+    Failed(String),
+}
+
+impl fmt::Display for ContainerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerState::None => write!(f, "none"),
+            ContainerState::Pulling => write!(f, "pulling"),
+            ContainerState::Running => write!(f, "running"),
+            ContainerState::Failed(reason) => write!(f, "failed: {reason}"),
+        }
+    }
+}
+
+impl ContainerState {
+    fn path_for(config: &VmConfig) -> Result<PathBuf> {
+        Ok(VmConfig::state_dir()?.join(format!("{}.container", config.name)))
+    }
+
+    /// Falls back to `None` if no container has ever been requested for this VM.
+    pub fn load(config: &VmConfig) -> Self {
+        Self::path_for(config)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, config: &VmConfig) -> Result<()> {
+        let path = Self::path_for(config)?;
+        let raw = serde_json::to_string(self).context("serializing container state")?;
+        std::fs::write(&path, raw).with_context(|| format!("writing container state to {}", path.display()))
+    }
+}
+
+/// Ask the guest agent to pull `spec.alias` from `spec.server` and create and
+/// start the container, tracking progress in the persisted container state.
+pub async fn create_container(config: &VmConfig, spec: &ContainerSpec) -> Result<ContainerState> {
+    info!(vm = %config.name, alias = %spec.alias, server = %spec.server, "requesting container provisioning");
+    ContainerState::Pulling.save(config)?;
+
+    // Open the status listener before the request so the guest's report
+    // can't race ahead of us, mirroring the boot/startup-listener ordering.
+    let status_listener = agent::bind_container_status_listener()?;
+
+    let response = match agent::launch_container(config, &spec.alias, &spec.server, &spec.user).await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!(vm = %config.name, error = %e, "failed to reach guest agent; marking container as failed");
+            let state = ContainerState::Failed(format!("failed to reach guest agent: {e}"));
+            state.save(config)?;
+            return Ok(state);
+        }
+    };
+    if let agent::Response::Err { message } = response {
+        let state = ContainerState::Failed(message);
+        state.save(config)?;
+        return Ok(state);
+    }
+
+    let state = match agent::accept_container_status(status_listener, config, CONTAINER_STATUS_TIMEOUT).await {
+        Ok(report) if report.running => ContainerState::Running,
+        Ok(report) => ContainerState::Failed(report.detail),
+        Err(e) => {
+            warn!(vm = %config.name, error = %e, "no container status report before timeout; marking container as failed");
+            ContainerState::Failed(format!("no status report from guest: {e}"))
+        }
+    };
+    state.save(config)?;
+    Ok(state)
+}
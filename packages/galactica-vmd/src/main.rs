@@ -1,6 +1,33 @@
-use anyhow::Result;
+mod agent;
+mod config;
+mod container;
+mod state;
+mod vmm;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use tracing::{info, Level};
+use tracing::{info, warn, Level};
+
+use config::{DiskConfig, NetConfig, SharedDir, VmConfig};
+use container::{ContainerSpec, ContainerState};
+use state::VmState;
+
+/// Parse a `--shared-dir TAG=HOST_PATH` argument.
+fn parse_shared_dir(raw: &str) -> Result<SharedDir, String> {
+    let (tag, host_path) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected TAG=HOST_PATH, got `{raw}`"))?;
+    Ok(SharedDir {
+        tag: tag.to_string(),
+        host_path: PathBuf::from(host_path),
+    })
+}
+
+/// How long `boot` waits for the guest agent to dial home before giving up.
+const GUEST_READY_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Parser)]
 #[command(name = "galactica-vmd")]
@@ -14,28 +41,113 @@ struct Cli {
 enum Commands {
     /// List all VMs
     List,
-    
-    /// Start a VM
+
+    /// Start a VM: equivalent to `start-vmm` followed by `boot`
     Start {
         /// Name of the VM to start
         name: String,
+
+        /// Provision and boot straight into the VM's default container (the built-in default image if none is set)
+        #[arg(long)]
+        into_container: bool,
+    },
+
+    /// Launch the VMM process and configure it with the VM's settings, without booting
+    StartVmm {
+        /// Name of the VM whose VMM to start
+        name: String,
+    },
+
+    /// Boot the guest in an already-configured VMM
+    Boot {
+        /// Name of the VM to boot
+        name: String,
+
+        /// Provision and boot straight into the VM's default container (the built-in default image if none is set)
+        #[arg(long)]
+        into_container: bool,
     },
-    
-    /// Stop a VM
+
+    /// Stop a VM, tearing down its VMM process entirely
     Stop {
         /// Name of the VM to stop
         name: String,
     },
-    
-    /// Create a new VM from template
+
+    /// Create a new VM
     Create {
-        /// Template name
-        #[arg(short, long)]
-        template: String,
-        
         /// VM name
         #[arg(short, long)]
         name: String,
+
+        /// Path to the guest kernel image
+        #[arg(long)]
+        kernel: PathBuf,
+
+        /// Kernel command line
+        #[arg(long, default_value = "console=hvc0 root=/dev/vda1 rw")]
+        cmdline: String,
+
+        /// Number of vCPUs
+        #[arg(long, default_value_t = 1)]
+        cpus: u8,
+
+        /// Memory size in MiB
+        #[arg(long, default_value_t = 512)]
+        memory: u64,
+
+        /// Disk image(s) to attach, e.g. `--disk root.img data.img`
+        #[arg(long, num_args = 1..)]
+        disk: Vec<PathBuf>,
+
+        /// Tap device(s) to attach, e.g. `--net tap0 --net tap1`
+        #[arg(long, num_args = 1..)]
+        net: Vec<String>,
+
+        /// Host directories to share into the guest via virtiofs, e.g.
+        /// `--shared-dir work=/home/user/work`
+        #[arg(long, value_parser = parse_shared_dir, num_args = 1..)]
+        shared_dir: Vec<SharedDir>,
+
+        /// Image alias for a container to provision automatically via `--into-container`
+        #[arg(long)]
+        default_container_image: Option<String>,
+
+        /// Image server to pull `--default-container-image` from
+        #[arg(long, default_value = container::DEFAULT_IMAGE_SERVER)]
+        default_container_server: String,
+
+        /// User to run the default container as
+        #[arg(long, default_value = container::DEFAULT_CONTAINER_USER)]
+        default_container_user: String,
+    },
+
+    /// Run a command inside a VM's guest agent
+    Exec {
+        /// Name of the VM to run the command in
+        name: String,
+
+        /// Command and arguments, e.g. `galactica-vmd exec myvm -- ls -la`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        cmd: Vec<String>,
+    },
+
+    /// Provision and start a container inside a running VM
+    CreateContainer {
+        /// Name of the VM to provision the container in
+        name: String,
+
+        /// Image alias to pull, e.g. `debian/stretch`
+        #[arg(long, default_value = container::DEFAULT_IMAGE_ALIAS)]
+        image: String,
+
+        /// Image server to pull from
+        #[arg(long, default_value = container::DEFAULT_IMAGE_SERVER)]
+        server: String,
+
+        /// User to run the container as
+        #[arg(long, default_value = container::DEFAULT_CONTAINER_USER)]
+        user: String,
     },
 }
 
@@ -51,27 +163,193 @@ async fn main() -> Result<()> {
     info!("🌌 Galactica VM Manager v{}", env!("CARGO_PKG_VERSION"));
 
     match cli.command {
-        Commands::List => {
-            info!("Listing VMs...");
-            // TODO: Implement VM listing
-            println!("No VMs found (not implemented yet)");
+        Commands::List => list().await?,
+        Commands::Start { name, into_container } => start(&name, into_container).await?,
+        Commands::StartVmm { name } => start_vmm(&name).await?,
+        Commands::Boot { name, into_container } => boot(&name, into_container).await?,
+        Commands::Stop { name } => stop(&name).await?,
+        Commands::Create {
+            name,
+            kernel,
+            cmdline,
+            cpus,
+            memory,
+            disk,
+            net,
+            shared_dir,
+            default_container_image,
+            default_container_server,
+            default_container_user,
+        } => {
+            let cid = VmConfig::next_cid()?;
+            create(VmConfig {
+                name,
+                kernel,
+                cmdline,
+                cpus,
+                memory_mb: memory,
+                disks: disk.into_iter().map(|path| DiskConfig { path }).collect(),
+                nets: net.into_iter().map(|tap| NetConfig { tap }).collect(),
+                cid,
+                shared_dirs: shared_dir,
+                default_container: default_container_image.map(|alias| ContainerSpec {
+                    alias,
+                    server: default_container_server,
+                    user: default_container_user,
+                }),
+            })
+            .await?
         }
-        Commands::Start { name } => {
-            info!("Starting VM: {}", name);
-            // TODO: Implement VM start
-            println!("Starting {} (not implemented yet)", name);
+        Commands::Exec { name, cmd } => exec(&name, cmd).await?,
+        Commands::CreateContainer { name, image, server, user } => {
+            create_container(&name, ContainerSpec { alias: image, server, user }).await?
         }
-        Commands::Stop { name } => {
-            info!("Stopping VM: {}", name);
-            // TODO: Implement VM stop
-            println!("Stopping {} (not implemented yet)", name);
+    }
+
+    Ok(())
+}
+
+async fn create(config: VmConfig) -> Result<()> {
+    info!(vm = %config.name, "creating VM");
+    config.save()?;
+    println!("Created {} ({} vCPUs, {} MiB)", config.name, config.cpus, config.memory_mb);
+    Ok(())
+}
+
+async fn start(name: &str, into_container: bool) -> Result<()> {
+    start_vmm(name).await?;
+    boot(name, into_container).await
+}
+
+async fn start_vmm(name: &str) -> Result<()> {
+    let config = VmConfig::load(name)?;
+    info!(vm = %name, "starting VMM");
+    vmm::virtiofs::spawn_all(&config).await?;
+    vmm::spawn_vmm(&config).await?;
+    vmm::configure(&config).await?;
+    VmState::Configured.save(&config)?;
+    println!("VMM for {name} configured, not booted");
+    Ok(())
+}
+
+async fn boot(name: &str, into_container: bool) -> Result<()> {
+    let config = VmConfig::load(name)?;
+    info!(vm = %name, "booting guest");
+
+    // Open the startup listener before boot so the guest's "I'm up" message
+    // can't race ahead of us.
+    let ready_listener = agent::bind_startup_listener()?;
+
+    vmm::boot(&config).await?;
+    VmState::Running.save(&config)?;
+
+    match agent::accept_ready(ready_listener, &config, GUEST_READY_TIMEOUT).await {
+        Ok(ready) => {
+            info!(vm = %name, hostname = %ready.hostname, "guest agent ready");
+            for shared_dir in &config.shared_dirs {
+                let target = shared_dir.guest_target();
+                match agent::mount_shared_dir(&config, &shared_dir.tag, &target).await {
+                    Ok(agent::Response::Ok { .. }) => {
+                        info!(vm = %name, tag = %shared_dir.tag, target = %target, "mounted shared dir")
+                    }
+                    Ok(agent::Response::Err { message }) => {
+                        warn!(vm = %name, tag = %shared_dir.tag, error = %message, "guest failed to mount shared dir")
+                    }
+                    Err(e) => warn!(vm = %name, tag = %shared_dir.tag, error = %e, "failed to request shared dir mount"),
+                }
+            }
+
+            if into_container {
+                let spec = config.default_container.clone().unwrap_or_default();
+                match container::create_container(&config, &spec).await {
+                    Ok(state) => info!(vm = %name, alias = %spec.alias, state = %state, "booted into default container"),
+                    Err(e) => warn!(vm = %name, error = %e, "failed to provision default container"),
+                }
+            }
         }
-        Commands::Create { template, name } => {
-            info!("Creating VM {} from template {}", name, template);
-            // TODO: Implement VM creation
-            println!("Creating {} from {} (not implemented yet)", name, template);
+        Err(e) => warn!(vm = %name, error = %e, "guest agent did not report ready in time"),
+    }
+
+    println!("Booted {name}");
+    Ok(())
+}
+
+async fn create_container(name: &str, spec: ContainerSpec) -> Result<()> {
+    let config = VmConfig::load(name)?;
+    let state = container::create_container(&config, &spec).await?;
+    match &state {
+        ContainerState::Running => println!("Container {} running in {name}", spec.alias),
+        other => println!("Container {} in {name}: {other}", spec.alias),
+    }
+    Ok(())
+}
+
+async fn exec(name: &str, cmd: Vec<String>) -> Result<()> {
+    let config = VmConfig::load(name)?;
+    let (command, args) = cmd.split_first().context("no command given")?;
+    match agent::run_command(&config, command, args.to_vec()).await? {
+        agent::Response::Ok { message } => {
+            println!("{message}");
+            Ok(())
         }
+        agent::Response::Err { message } => anyhow::bail!("guest agent error: {message}"),
     }
+}
 
+async fn stop(name: &str) -> Result<()> {
+    let config = VmConfig::load(name)?;
+    info!(vm = %name, "stopping VM");
+    vmm::shutdown_and_delete(&config).await?;
+    VmState::VmmStopped.save(&config)?;
+    println!("Stopped {name}");
     Ok(())
-}
\ No newline at end of file
+}
+
+async fn list() -> Result<()> {
+    let names = VmConfig::list()?;
+    if names.is_empty() {
+        println!("No VMs found");
+        return Ok(());
+    }
+    for name in names {
+        let (state, ready, container) = match VmConfig::load(&name) {
+            Ok(config) => {
+                let state = reconciled_state(&config).await;
+                let ready = state == VmState::Running && agent::probe_ready(&config).await;
+                let container = ContainerState::load(&config);
+                (state, ready, container)
+            }
+            Err(e) => {
+                warn!(vm = %name, error = %e, "failed to load VM config");
+                (VmState::VmmStopped, false, ContainerState::None)
+            }
+        };
+        println!("{name}\t{state}\tagent-ready={ready}\tcontainer={container}");
+    }
+    Ok(())
+}
+
+/// Read the persisted state and, if it claims `Running`, check whether the
+/// guest powered itself off since the last time we looked. If so, demote it
+/// to `Configured` rather than rebooting it — the VMM process is still up.
+async fn reconciled_state(config: &VmConfig) -> VmState {
+    let persisted = VmState::load(config).unwrap_or(VmState::VmmStopped);
+    if persisted != VmState::Running {
+        return persisted;
+    }
+    match vmm::is_guest_running(config).await {
+        Ok(true) => VmState::Running,
+        Ok(false) => {
+            info!(vm = %config.name, "guest powered itself off; leaving VMM configured and idle");
+            let _ = VmState::Configured.save(config);
+            VmState::Configured
+        }
+        Err(e) => {
+            // A query failure doesn't tell us the VMM is gone — it could be a
+            // transient socket hiccup. Keep reporting the persisted state
+            // rather than silently downgrading a live VMM to `VmmStopped`.
+            warn!(vm = %config.name, error = %e, "failed to query guest power state; keeping persisted state");
+            persisted
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::config::VmConfig;
+
+/// Lifecycle state of a VM's cloud-hypervisor process, tracked independently
+/// of whether the guest inside it happens to be booted.
+///
+/// A guest powering itself off from the inside moves a VM from `Running`
+/// back to `Configured`, not to `VmmStopped` — the VMM process is left
+/// running and idle rather than torn down, so it's indistinguishable from a
+/// VM that was configured but never booted. Only an explicit `Stop` tears
+/// the VMM process down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmState {
+    /// No cloud-hypervisor process is running for this VM.
+    VmmStopped,
+    /// The VMM process is up and has a guest configured (`vm.create`) but
+    /// not currently booted.
+    Configured,
+    /// The VMM process is up and the guest is booted and running.
+    Running,
+}
+
+impl VmState {
+    fn as_str(self) -> &'static str {
+        match self {
+            VmState::VmmStopped => "VmmStopped",
+            VmState::Configured => "Configured",
+            VmState::Running => "Running",
+        }
+    }
+
+    fn parse(raw: &str) -> Self {
+        match raw.trim() {
+            "Configured" => VmState::Configured,
+            "Running" => VmState::Running,
+            _ => VmState::VmmStopped,
+        }
+    }
+
+    fn path_for(config: &VmConfig) -> Result<PathBuf> {
+        Ok(VmConfig::state_dir()?.join(format!("{}.state", config.name)))
+    }
+
+    pub fn load(config: &VmConfig) -> Result<Self> {
+        let path = Self::path_for(config)?;
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => Ok(Self::parse(&raw)),
+            Err(_) => Ok(VmState::VmmStopped),
+        }
+    }
+
+    pub fn save(self, config: &VmConfig) -> Result<()> {
+        let path = Self::path_for(config)?;
+        std::fs::write(&path, self.as_str())
+            .with_context(|| format!("writing VM state to {}", path.display()))
+    }
+}
+
+impl fmt::Display for VmState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
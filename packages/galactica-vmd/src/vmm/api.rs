@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+use crate::config::VmConfig;
+
+use super::vm_create_payload;
+
+/// Client for cloud-hypervisor's HTTP-over-Unix-socket API.
+///
+/// The VMM only exposes a handful of endpoints we care about, so rather than
+/// pull in a full HTTP stack this speaks just enough HTTP/1.1 to send a PUT
+/// or GET request and read back the status line. `vm.info` is a GET; the
+/// mutating `vm.*` actions are PUTs.
+pub struct VmmClient {
+    socket: PathBuf,
+}
+
+impl VmmClient {
+    pub fn new(socket: PathBuf) -> Self {
+        Self { socket }
+    }
+
+    async fn request(&self, method: &str, path: &str, body: Option<&str>) -> Result<String> {
+        let mut stream = UnixStream::connect(&self.socket)
+            .await
+            .with_context(|| format!("connecting to VMM socket {}", self.socket.display()))?;
+
+        let body = body.unwrap_or_default();
+        let request = format!(
+            "{method} /api/v1/{path} HTTP/1.1\r\n\
+             Host: localhost\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\r\n\
+             {body}",
+            body.len()
+        );
+
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .with_context(|| format!("writing VMM request to {path}"))?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .with_context(|| format!("reading VMM response from {path}"))?;
+
+        let status_line = response
+            .lines()
+            .next()
+            .with_context(|| format!("empty response from VMM for {path}"))?;
+        if !status_line.contains(" 200") && !status_line.contains(" 204") {
+            bail!("VMM API {path} returned {status_line}");
+        }
+        Ok(response)
+    }
+
+    async fn put(&self, path: &str, body: Option<&str>) -> Result<String> {
+        self.request("PUT", path, body).await
+    }
+
+    async fn get(&self, path: &str) -> Result<String> {
+        self.request("GET", path, None).await
+    }
+
+    pub async fn vm_create(&self, config: &VmConfig) -> Result<()> {
+        let payload = vm_create_payload(config)?;
+        self.put("vm.create", Some(&payload)).await?;
+        Ok(())
+    }
+
+    pub async fn vm_boot(&self) -> Result<()> {
+        self.put("vm.boot", None).await?;
+        Ok(())
+    }
+
+    pub async fn vm_shutdown(&self) -> Result<()> {
+        self.put("vm.shutdown", None).await?;
+        Ok(())
+    }
+
+    pub async fn vm_delete(&self) -> Result<()> {
+        self.put("vm.delete", None).await?;
+        Ok(())
+    }
+
+    /// Best-effort power state query against `vm.info`, used by `List`.
+    pub async fn power_state(&self) -> Result<String> {
+        let response = self.get("vm.info").await?;
+        if let Some(idx) = response.find("\"state\":\"") {
+            let rest = &response[idx + 9..];
+            if let Some(end) = rest.find('"') {
+                return Ok(rest[..end].to_string());
+            }
+        }
+        Ok("Unknown".to_string())
+    }
+}
@@ -0,0 +1,136 @@
+mod api;
+pub mod virtiofs;
+
+pub use api::VmmClient;
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::net::UnixStream;
+use tokio::process::{Child, Command};
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::config::VmConfig;
+
+const CLOUD_HYPERVISOR_BIN: &str = "cloud-hypervisor";
+
+/// How long to wait for a sidecar process (cloud-hypervisor, virtiofsd) to
+/// create and bind its Unix socket after fork before giving up.
+const SOCKET_READY_TIMEOUT: Duration = Duration::from_secs(5);
+const SOCKET_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Spawn a cloud-hypervisor process bound to `config`'s API socket.
+///
+/// The process comes up idle: it accepts API calls immediately but doesn't
+/// boot anything until a subsequent `vm.create` + `vm.boot` call is made
+/// against it. The returned handle is deliberately not kept alive by the
+/// caller beyond this call — tokio does not kill a child on drop, so the VMM
+/// keeps running as its own process after `galactica-vmd` exits, and stays up
+/// across a guest powering itself off.
+pub async fn spawn_vmm(config: &VmConfig) -> Result<Child> {
+    let socket = config.socket_path()?;
+    if socket.exists() {
+        std::fs::remove_file(&socket)
+            .with_context(|| format!("removing stale socket {}", socket.display()))?;
+    }
+
+    let child = Command::new(CLOUD_HYPERVISOR_BIN)
+        .arg("--api-socket")
+        .arg(&socket)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("spawning {CLOUD_HYPERVISOR_BIN}"))?;
+
+    wait_for_socket(&socket).await.context("waiting for VMM API socket")?;
+
+    info!(vm = %config.name, socket = %socket.display(), "started cloud-hypervisor VMM process");
+    Ok(child)
+}
+
+/// cloud-hypervisor and virtiofsd both create and bind their Unix sockets
+/// asynchronously after fork, so the socket path may not exist (or may not
+/// yet accept connections) the instant `spawn` returns. Poll for it to come
+/// up before issuing a request against it, rather than racing it.
+pub(crate) async fn wait_for_socket(socket: &std::path::Path) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + SOCKET_READY_TIMEOUT;
+    loop {
+        match UnixStream::connect(socket).await {
+            Ok(_) => return Ok(()),
+            Err(e) if tokio::time::Instant::now() < deadline => {
+                let _ = e;
+                sleep(SOCKET_POLL_INTERVAL).await;
+            }
+            Err(e) => {
+                bail!(
+                    "timed out after {:?} waiting for socket {} to come up: {e}",
+                    SOCKET_READY_TIMEOUT,
+                    socket.display()
+                )
+            }
+        }
+    }
+}
+
+/// Configure a running VMM with `vm.create` without booting it.
+///
+/// This is the `start-vmm` half of starting a VM: afterwards the VMM process
+/// is up and holds the guest config, but no vCPUs are running.
+pub async fn configure(config: &VmConfig) -> Result<()> {
+    VmmClient::new(config.socket_path()?).vm_create(config).await
+}
+
+/// Boot a guest in a VMM that has already been configured.
+pub async fn boot(config: &VmConfig) -> Result<()> {
+    VmmClient::new(config.socket_path()?).vm_boot().await
+}
+
+/// Shut the guest down and tear down the VMM process via its API.
+pub async fn shutdown_and_delete(config: &VmConfig) -> Result<()> {
+    let client = VmmClient::new(config.socket_path()?);
+    if let Err(e) = client.vm_shutdown().await {
+        warn!(vm = %config.name, error = %e, "vm.shutdown failed, deleting anyway");
+    }
+    client.vm_delete().await
+}
+
+/// Query the running VMM for this VM's current power state, used by `List`.
+pub async fn power_state(config: &VmConfig) -> Result<String> {
+    VmmClient::new(config.socket_path()?).power_state().await
+}
+
+/// Whether the VMM currently reports a booted, running guest.
+///
+/// Used to detect the case where the guest powered itself off from the
+/// inside: cloud-hypervisor's own `vm.info` state moves from `"Running"` to
+/// `"Shutdown"` while the VMM process itself stays up, which is exactly the
+/// signal the caller needs to demote a VM from [`crate::state::VmState::Running`]
+/// to [`crate::state::VmState::Configured`] instead of rebooting it.
+pub async fn is_guest_running(config: &VmConfig) -> Result<bool> {
+    Ok(power_state(config).await?.eq_ignore_ascii_case("running"))
+}
+
+/// Build the cloud-hypervisor `vm.create` JSON payload from our own config.
+pub fn vm_create_payload(config: &VmConfig) -> Result<String> {
+    let payload = serde_json::json!({
+        "kernel": { "path": config.kernel },
+        "cmdline": { "args": config.cmdline },
+        "cpus": { "boot_vcpus": config.cpus, "max_vcpus": config.cpus },
+        "memory": { "size": config.memory_mb * 1024 * 1024 },
+        "disks": config.disks.iter().map(|d| serde_json::json!({ "path": d.path })).collect::<Vec<_>>(),
+        "net": config.nets.iter().map(|n| serde_json::json!({ "tap": n.tap })).collect::<Vec<_>>(),
+        "fs": config.shared_dirs.iter().map(|s| {
+            let socket = config.virtiofsd_socket_path(&s.tag).ok();
+            serde_json::json!({
+                "tag": s.tag,
+                "socket": socket,
+                "num_queues": 1,
+                "queue_size": 1024,
+            })
+        }).collect::<Vec<_>>(),
+    });
+    serde_json::to_string(&payload).context("serializing vm.create payload")
+}
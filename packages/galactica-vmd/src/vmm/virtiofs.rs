@@ -0,0 +1,59 @@
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+use tokio::process::{Child, Command};
+use tracing::info;
+
+use crate::config::{SharedDir, VmConfig};
+
+use super::wait_for_socket;
+
+const VIRTIOFSD_BIN: &str = "virtiofsd";
+
+/// Spawn a virtiofsd sidecar serving `shared_dir` on its own socket.
+///
+/// One instance is spawned per shared directory; the cloud-hypervisor `fs`
+/// device for this tag then points at the resulting socket.
+pub async fn spawn(config: &VmConfig, shared_dir: &SharedDir) -> Result<Child> {
+    let socket = config.virtiofsd_socket_path(&shared_dir.tag)?;
+    if socket.exists() {
+        std::fs::remove_file(&socket)
+            .with_context(|| format!("removing stale virtiofsd socket {}", socket.display()))?;
+    }
+
+    let child = Command::new(VIRTIOFSD_BIN)
+        .arg("--socket-path")
+        .arg(&socket)
+        .arg("--shared-dir")
+        .arg(&shared_dir.host_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("spawning {VIRTIOFSD_BIN} for tag {}", shared_dir.tag))?;
+
+    wait_for_socket(&socket)
+        .await
+        .with_context(|| format!("waiting for virtiofsd socket for tag {}", shared_dir.tag))?;
+
+    info!(
+        vm = %config.name,
+        tag = %shared_dir.tag,
+        host_path = %shared_dir.host_path.display(),
+        socket = %socket.display(),
+        "started virtiofsd sidecar"
+    );
+    Ok(child)
+}
+
+/// Spawn a virtiofsd sidecar for every shared directory configured on this VM.
+///
+/// Like the VMM process itself, each sidecar outlives this call; dropping
+/// the returned handles does not kill them.
+pub async fn spawn_all(config: &VmConfig) -> Result<Vec<Child>> {
+    let mut children = Vec::with_capacity(config.shared_dirs.len());
+    for shared_dir in &config.shared_dirs {
+        children.push(spawn(config, shared_dir).await?);
+    }
+    Ok(children)
+}